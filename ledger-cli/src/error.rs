@@ -0,0 +1,58 @@
+//! Typed APDU status errors: carries the raw status word and, where recognised, a
+//! context-sensitive hint so a wrong-app or locked-device error doesn't just look like a bare
+//! failure.
+
+use std::fmt;
+
+use ledger_transport::APDUErrorCode;
+use serde::Serialize;
+
+/// An APDU exchange that completed but returned a non-success status word
+#[derive(Clone, Debug, Serialize)]
+pub struct ApduError {
+    /// Raw 2-byte status word returned by the device
+    pub status_word: u16,
+    /// Decoded error code, if the status word maps to a known one
+    pub error_code: Option<String>,
+    /// Actionable hint for the most common failure modes
+    pub hint: Option<&'static str>,
+}
+
+impl ApduError {
+    /// Build from a status word that decoded to a known `APDUErrorCode`
+    pub fn known(code: APDUErrorCode, status_word: u16) -> Self {
+        Self { status_word, error_code: Some(format!("{:?}", code)), hint: hint_for(Some(code)) }
+    }
+
+    /// Build from a status word that didn't decode to any known `APDUErrorCode`
+    pub fn unknown(status_word: u16) -> Self {
+        Self { status_word, error_code: None, hint: hint_for(None) }
+    }
+}
+
+impl fmt::Display for ApduError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "APDU error: status word 0x{:04x}", self.status_word)?;
+        if let Some(code) = &self.error_code {
+            write!(f, " ({})", code)?;
+        }
+        if let Some(hint) = self.hint {
+            write!(f, " - {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ApduError {}
+
+fn hint_for(code: Option<APDUErrorCode>) -> Option<&'static str> {
+    match code {
+        Some(APDUErrorCode::InsNotSupported) | Some(APDUErrorCode::ClaNotSupported) => {
+            Some("Is the correct application open on the device?")
+        }
+        Some(APDUErrorCode::ConditionsNotSatisfied) => {
+            Some("Unlock the device and confirm any pending prompt, then try again")
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,370 @@
+//! Ethereum app commands (CLA `0xe0`), including the chunked APDU exchange the app requires
+//! for payloads that exceed a single APDU's data limit (RLP transactions, ERC-20 token info).
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::ops::Deref;
+
+use clap::Parser;
+use ledger_transport::{APDUAnswer, APDUCommand, APDUErrorCode, Exchange};
+use serde::Serialize;
+
+use crate::{print_result, ApduError, OutputFormat};
+
+const CLA_ETH: u8 = 0xe0;
+const INS_GET_ADDRESS: u8 = 0x02;
+const INS_SIGN_TRANSACTION: u8 = 0x04;
+const INS_GET_APP_CONFIGURATION: u8 = 0x06;
+const INS_PROVIDE_ERC20_TOKEN_INFO: u8 = 0x0a;
+
+/// Ledger's single-APDU data limit is 255 bytes; stay comfortably under it for chunking.
+const MAX_CHUNK_SIZE: usize = 250;
+
+#[derive(Clone, PartialEq, Debug, Parser)]
+pub enum EthCommands {
+    /// Fetch the address for a BIP-32 path
+    GetAddress {
+        /// BIP-32 derivation path, e.g. "44'/60'/0'/0/0"
+        path: String,
+
+        /// Require the address to be confirmed and displayed on the device
+        #[clap(long)]
+        display: bool,
+
+        /// Also return the BIP-32 chain code for the path
+        #[clap(long)]
+        chain_code: bool,
+    },
+
+    /// Fetch the Ethereum app configuration
+    GetAppConfiguration,
+
+    /// Provide ERC-20 token info so the device can display token transfers by name
+    ProvideErc20TokenInfo {
+        /// Hex-encoded token info blob (ticker, decimals, contract address, chain id, signature)
+        info: String,
+    },
+
+    /// Sign a raw RLP-encoded transaction
+    SignTransaction {
+        /// BIP-32 derivation path
+        path: String,
+
+        /// Hex-encoded RLP transaction payload
+        tx: String,
+    },
+}
+
+/// Parsed response to `get-address`
+#[derive(Clone, Debug, Serialize)]
+pub struct EthAddress {
+    pub public_key: Vec<u8>,
+    pub address: String,
+    pub chain_code: Option<[u8; 32]>,
+}
+
+impl TryFrom<&[u8]> for EthAddress {
+    type Error = anyhow::Error;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        let mut offset = 0;
+
+        let key_len = *data
+            .get(offset)
+            .ok_or_else(|| anyhow::anyhow!("truncated get-address response"))? as usize;
+        offset += 1;
+        let public_key = data
+            .get(offset..offset + key_len)
+            .ok_or_else(|| anyhow::anyhow!("truncated public key in get-address response"))?
+            .to_vec();
+        offset += key_len;
+
+        let addr_len = *data
+            .get(offset)
+            .ok_or_else(|| anyhow::anyhow!("truncated get-address response"))? as usize;
+        offset += 1;
+        let address_bytes = data
+            .get(offset..offset + addr_len)
+            .ok_or_else(|| anyhow::anyhow!("truncated address in get-address response"))?;
+        let address = String::from_utf8(address_bytes.to_vec())?;
+        offset += addr_len;
+
+        let chain_code = data
+            .get(offset..offset + 32)
+            .map(|b| b.try_into().expect("slice is exactly 32 bytes"));
+
+        Ok(EthAddress { public_key, address, chain_code })
+    }
+}
+
+/// Parsed `v`/`r`/`s` signature returned by `sign-transaction`
+#[derive(Clone, Debug, Serialize)]
+pub struct EthSignature {
+    pub v: u8,
+    pub r: [u8; 32],
+    pub s: [u8; 32],
+}
+
+impl TryFrom<&[u8]> for EthSignature {
+    type Error = anyhow::Error;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() != 65 {
+            return Err(anyhow::anyhow!("unexpected sign-transaction response length: {}", data.len()));
+        }
+
+        Ok(EthSignature {
+            v: data[0],
+            r: data[1..33].try_into().expect("slice is exactly 32 bytes"),
+            s: data[33..65].try_into().expect("slice is exactly 32 bytes"),
+        })
+    }
+}
+
+/// Encode a BIP-32 path string (e.g. `44'/60'/0'/0/0`) the way the Ethereum app expects: one
+/// byte giving the element count, then each element as a big-endian u32 with hardened indices
+/// ORed with `0x8000_0000`.
+fn encode_bip32_path(path: &str) -> anyhow::Result<Vec<u8>> {
+    let elements = path
+        .split('/')
+        .map(|e| {
+            let hardened = e.ends_with('\'') || e.ends_with('h');
+            let index: u32 = e.trim_end_matches(['\'', 'h']).parse()?;
+            Ok::<_, anyhow::Error>(if hardened { index | 0x8000_0000 } else { index })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut out = Vec::with_capacity(1 + elements.len() * 4);
+    out.push(elements.len() as u8);
+    for e in elements {
+        out.extend_from_slice(&e.to_be_bytes());
+    }
+    Ok(out)
+}
+
+/// Check an exchanged APDU's status word, turning anything but `NoError` into an `ApduError`
+fn check_status<B>(response: &APDUAnswer<B>) -> anyhow::Result<()>
+where
+    B: Deref<Target = [u8]>,
+{
+    match response.error_code() {
+        Ok(APDUErrorCode::NoError) => Ok(()),
+        Ok(code) => Err(ApduError::known(code, response.retcode()).into()),
+        Err(_) => Err(ApduError::unknown(response.retcode()).into()),
+    }
+}
+
+/// Send a single APDU with explicit `p1`/`p2` and return its answer. Use this for instructions
+/// where `p1`/`p2` carry command-specific meaning (e.g. `get-address`'s display/chain-code
+/// flags) rather than chunk continuation.
+async fn exchange_single<T, E>(t: &T, ins: u8, p1: u8, p2: u8, data: &[u8]) -> anyhow::Result<APDUAnswer<T::AnswerType>>
+where
+    T: Exchange<Error = E>,
+    E: Error + Sync + Send + 'static,
+{
+    let command = APDUCommand { cla: CLA_ETH, ins, p1, p2, data: data.to_vec() };
+
+    let response = t.exchange(&command).await?;
+    check_status(&response)?;
+
+    Ok(response)
+}
+
+/// Send `data` as a sequence of `<=MAX_CHUNK_SIZE`-byte APDUs: `p1 = 0x00` on the first chunk,
+/// `0x80` on every subsequent one, `p2` held constant throughout. Only use this for
+/// instructions that actually need chunking (`sign-transaction`, `provide-erc20-token-info`) -
+/// `p1` is reserved for chunk continuation here, so it can't carry any other meaning. Each chunk
+/// is checked for `APDUErrorCode::NoError` before the next is sent; the final chunk's answer is
+/// returned.
+async fn exchange_chunked<T, E>(t: &T, ins: u8, p2: u8, data: &[u8]) -> anyhow::Result<APDUAnswer<T::AnswerType>>
+where
+    T: Exchange<Error = E>,
+    E: Error + Sync + Send + 'static,
+{
+    let chunks: Vec<&[u8]> = if data.is_empty() {
+        vec![&[][..]]
+    } else {
+        data.chunks(MAX_CHUNK_SIZE).collect()
+    };
+
+    let mut last = None;
+    for (i, chunk) in chunks.into_iter().enumerate() {
+        let p1 = if i == 0 { 0x00 } else { 0x80 };
+        let command = APDUCommand {
+            cla: CLA_ETH,
+            ins,
+            p1,
+            p2,
+            data: chunk.to_vec(),
+        };
+
+        let response = t.exchange(&command).await?;
+        check_status(&response)?;
+
+        last = Some(response);
+    }
+
+    Ok(last.expect("at least one chunk is always sent, even for empty data"))
+}
+
+/// Execute an Ethereum app command against the provided transport
+pub async fn execute<T, E>(t: &T, cmd: EthCommands, format: OutputFormat) -> anyhow::Result<()>
+where
+    T: Exchange<Error = E>,
+    E: Error + Sync + Send + 'static,
+{
+    match cmd {
+        EthCommands::GetAddress { path, display, chain_code } => {
+            let data = encode_bip32_path(&path)?;
+            let p1 = if display { 0x01 } else { 0x00 };
+            let p2 = if chain_code { 0x01 } else { 0x00 };
+            let response = exchange_single(t, INS_GET_ADDRESS, p1, p2, &data).await?;
+
+            let address = EthAddress::try_from(response.data())?;
+            print_result(format, &address)?;
+        }
+        EthCommands::GetAppConfiguration => {
+            let response = exchange_single(t, INS_GET_APP_CONFIGURATION, 0x00, 0x00, &[]).await?;
+            print_result(format, &response.data().to_vec())?;
+        }
+        EthCommands::ProvideErc20TokenInfo { info: token_info } => {
+            let data = hex::decode(token_info.trim_start_matches("0x"))?;
+            exchange_chunked(t, INS_PROVIDE_ERC20_TOKEN_INFO, 0x00, &data).await?;
+            log::info!("erc20 token info provided");
+        }
+        EthCommands::SignTransaction { path, tx } => {
+            let mut data = encode_bip32_path(&path)?;
+            data.extend_from_slice(&hex::decode(tx.trim_start_matches("0x"))?);
+
+            let response = exchange_chunked(t, INS_SIGN_TRANSACTION, 0x00, &data).await?;
+
+            let signature = EthSignature::try_from(response.data())?;
+            print_result(format, &signature)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::ops::Deref;
+    use std::sync::Mutex as StdMutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+
+    #[test]
+    fn encode_bip32_path_standard() {
+        let encoded = encode_bip32_path("44'/60'/0'/0/0").unwrap();
+        assert_eq!(
+            encoded,
+            vec![
+                5, //
+                0x80, 0x00, 0x00, 0x2c, //
+                0x80, 0x00, 0x00, 0x3c, //
+                0x80, 0x00, 0x00, 0x00, //
+                0x00, 0x00, 0x00, 0x00, //
+                0x00, 0x00, 0x00, 0x00, //
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_bip32_path_h_suffix_matches_apostrophe() {
+        assert_eq!(
+            encode_bip32_path("44h/60h/0h/0/0").unwrap(),
+            encode_bip32_path("44'/60'/0'/0/0").unwrap()
+        );
+    }
+
+    #[test]
+    fn encode_bip32_path_rejects_non_numeric_element() {
+        assert!(encode_bip32_path("44'/sixty/0").is_err());
+    }
+
+    #[test]
+    fn eth_address_parses_without_chain_code() {
+        let mut data = vec![2u8, 0xaa, 0xbb]; // 2-byte public key
+        data.push(3); // address length
+        data.extend_from_slice(b"0xA");
+
+        let address = EthAddress::try_from(data.as_slice()).unwrap();
+        assert_eq!(address.public_key, vec![0xaa, 0xbb]);
+        assert_eq!(address.address, "0xA");
+        assert!(address.chain_code.is_none());
+    }
+
+    #[test]
+    fn eth_address_parses_with_chain_code() {
+        let mut data = vec![1u8, 0xaa, 1, b'a'];
+        data.extend_from_slice(&[0x11; 32]);
+
+        let address = EthAddress::try_from(data.as_slice()).unwrap();
+        assert_eq!(address.chain_code, Some([0x11; 32]));
+    }
+
+    #[test]
+    fn eth_address_rejects_truncated_response() {
+        assert!(EthAddress::try_from(&[5u8, 1, 2][..]).is_err());
+    }
+
+    #[test]
+    fn eth_signature_parses_65_bytes() {
+        let mut data = vec![0x1bu8];
+        data.extend_from_slice(&[0x01; 32]);
+        data.extend_from_slice(&[0x02; 32]);
+
+        let signature = EthSignature::try_from(data.as_slice()).unwrap();
+        assert_eq!(signature.v, 0x1b);
+        assert_eq!(signature.r, [0x01; 32]);
+        assert_eq!(signature.s, [0x02; 32]);
+    }
+
+    #[test]
+    fn eth_signature_rejects_wrong_length() {
+        assert!(EthSignature::try_from(&[0u8; 64][..]).is_err());
+    }
+
+    /// Records the `(p1, p2, data.len())` of every APDU it's handed and always answers success
+    struct MockTransport {
+        calls: StdMutex<Vec<(u8, u8, usize)>>,
+    }
+
+    #[async_trait]
+    impl Exchange for MockTransport {
+        type Error = Infallible;
+        type AnswerType = Vec<u8>;
+
+        async fn exchange<I>(&self, command: &APDUCommand<I>) -> Result<APDUAnswer<Self::AnswerType>, Self::Error>
+        where
+            I: Deref<Target = [u8]> + Send + Sync,
+        {
+            self.calls.lock().unwrap().push((command.p1, command.p2, command.data.len()));
+            Ok(APDUAnswer::from_answer(vec![0x90, 0x00]).unwrap())
+        }
+    }
+
+    #[tokio::test]
+    async fn exchange_chunked_uses_continuation_p1_after_first_chunk() {
+        let transport = MockTransport { calls: StdMutex::new(Vec::new()) };
+        let data = vec![0xabu8; MAX_CHUNK_SIZE + 10];
+
+        exchange_chunked(&transport, INS_SIGN_TRANSACTION, 0x00, &data).await.unwrap();
+
+        let calls = transport.calls.lock().unwrap();
+        assert_eq!(*calls, vec![(0x00, 0x00, MAX_CHUNK_SIZE), (0x80, 0x00, 10)]);
+    }
+
+    #[tokio::test]
+    async fn exchange_chunked_sends_one_empty_chunk_for_empty_data() {
+        let transport = MockTransport { calls: StdMutex::new(Vec::new()) };
+
+        exchange_chunked(&transport, INS_GET_APP_CONFIGURATION, 0x00, &[]).await.unwrap();
+
+        let calls = transport.calls.lock().unwrap();
+        assert_eq!(*calls, vec![(0x00, 0x00, 0)]);
+    }
+}
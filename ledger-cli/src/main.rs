@@ -7,6 +7,17 @@ use ledger_zondax_generic::{DeviceInfo, AppInfo, Version};
 use strum::{Display, EnumString, EnumVariantNames};
 use log::LevelFilter;
 
+mod error;
+pub(crate) use error::ApduError;
+
+mod eth;
+use eth::EthCommands;
+
+mod serve;
+
+mod transport_hid;
+use transport_hid::{HidOptions, TransportHid};
+
 /// Ledger command line utility
 #[derive(Clone, PartialEq, Debug, Parser)]
 pub struct Options {
@@ -18,6 +29,49 @@ pub struct Options {
     /// Enable verbose logging
     #[clap(long, default_value = "debug")]
     level: LevelFilter,
+
+    /// Output format for command results
+    #[clap(long, value_enum, default_value_t = OutputFormat::Human)]
+    format: OutputFormat,
+}
+
+/// Output format for command results, kept separate from the `log` stream so the tool can be
+/// driven from other processes
+#[derive(Clone, Copy, PartialEq, Debug, clap::ValueEnum)]
+pub enum OutputFormat {
+    Human,
+    Json,
+}
+
+/// Render a command's result according to `format`: a `{:#?}` debug dump on stderr via `log`
+/// for humans, or a `serde_json` object on stdout for scripting.
+pub(crate) fn print_result<T>(format: OutputFormat, value: &T) -> anyhow::Result<()>
+where
+    T: serde::Serialize + std::fmt::Debug,
+{
+    match format {
+        OutputFormat::Human => log::info!("{:#?}", value),
+        OutputFormat::Json => println!("{}", serde_json::to_string(value)?),
+    }
+
+    Ok(())
+}
+
+/// Render an error according to `format`, returning the process exit code to use. An
+/// `ApduError` is surfaced with its status word and hint rather than flattened to a string, in
+/// both output formats.
+pub(crate) fn print_error(format: OutputFormat, err: &anyhow::Error) -> i32 {
+    match (format, err.downcast_ref::<ApduError>()) {
+        (OutputFormat::Human, _) => log::error!("{:?}", err),
+        (OutputFormat::Json, Some(apdu_err)) => {
+            println!("{}", serde_json::json!({ "error": apdu_err }))
+        }
+        (OutputFormat::Json, None) => {
+            println!("{}", serde_json::json!({ "error": err.to_string() }))
+        }
+    }
+
+    1
 }
 
 #[derive(Clone, PartialEq, Debug, Parser)]
@@ -33,12 +87,24 @@ pub enum Commands {
         /// Application ADPU class
         cla: u8,
     },
+
+    /// Ethereum app commands
+    Eth{
+        #[clap(subcommand)]
+        cmd: EthCommands,
+    },
 }
 
 #[derive(Clone, PartialEq, Debug, Parser, Display)]
 pub enum Transport {
     /// USB HID
-    Hid,
+    Hid{
+        #[clap(flatten)]
+        opts: HidOptions,
+
+        #[clap(subcommand)]
+        cmd: HidCommands,
+    },
     /// Bluetooth Low Energy
     Ble,
     /// TCP (Speculos simulator)
@@ -51,6 +117,37 @@ pub enum Transport {
     },
     /// Zemu simulator
     Zemu,
+
+    /// Relay/bridge server: expose one underlying transport to multiple TCP clients
+    Serve{
+        #[clap(subcommand)]
+        backing: ServeBacking,
+
+        /// Address to bind the relay server on
+        #[clap(long, default_value = "127.0.0.1:9999")]
+        bind: std::net::SocketAddr,
+    },
+}
+
+#[derive(Clone, PartialEq, Debug, Parser)]
+pub enum HidCommands {
+    /// List connected Ledger devices
+    List,
+
+    #[clap(flatten)]
+    App(Commands),
+}
+
+/// Transport backing a `serve` relay
+#[derive(Clone, PartialEq, Debug, Parser)]
+pub enum ServeBacking {
+    /// Serve over USB HID
+    Hid{
+        #[clap(flatten)]
+        opts: HidOptions,
+    },
+    /// Serve over TCP (not yet implemented)
+    Tcp,
 }
 
 #[tokio::main]
@@ -59,22 +156,59 @@ async fn main() -> anyhow::Result<()> {
     // Parse command line arguments
     let args = Options::parse();
 
-    // Setup logging
-    simplelog::SimpleLogger::init(args.level, simplelog::Config::default()).unwrap();
+    // Setup logging. `WriteLogger` is pinned to stderr so `--format json` can put its result
+    // payload on stdout without log lines interleaving with it.
+    simplelog::WriteLogger::init(args.level, simplelog::Config::default(), std::io::stderr()).unwrap();
+
+    let format = args.format;
 
-    // Connect to transport and execute commands
-    match args.transport {
+    // Connect to transport and execute commands, reporting any error in the selected format
+    if let Err(e) = run(args.transport, format).await {
+        let code = print_error(format, &e);
+        std::process::exit(code);
+    }
+
+    Ok(())
+}
+
+/// Connect to the selected transport and execute the requested command
+async fn run(transport: Transport, format: OutputFormat) -> anyhow::Result<()> {
+    match transport {
+        Transport::Hid{ opts, cmd } => match cmd {
+            HidCommands::List => {
+                let devices = transport_hid::list_devices(&opts)?;
+                for d in devices {
+                    println!(
+                        "{:04x}:{:04x} serial={} product={} interface={}",
+                        d.vendor_id,
+                        d.product_id,
+                        d.serial_number.as_deref().unwrap_or("-"),
+                        d.product_string.as_deref().unwrap_or("-"),
+                        d.interface_number,
+                    );
+                }
+            }
+            HidCommands::App(cmd) => {
+                let t = TransportHid::new(opts)?;
+
+                execute(t, cmd, format).await?;
+            }
+        },
         Transport::Tcp{ opts, cmd } => {
             let t = TransportTcp::new(opts).await?;
 
-            execute(t, cmd).await?;
+            execute(t, cmd, format).await?;
         }
-        _ => todo!("{} transport not yet implemented", args.transport),
-    };
-
-    // Execute command
+        Transport::Serve{ backing, bind } => match backing {
+            ServeBacking::Hid{ opts } => {
+                let t = TransportHid::new(opts)?;
 
-    println!("Hello, world!");
+                serve::serve(t, bind).await?;
+            }
+            ServeBacking::Tcp => todo!("tcp backing for serve not yet implemented"),
+        },
+        _ => todo!("{} transport not yet implemented", transport),
+    };
 
     Ok(())
 }
@@ -86,11 +220,16 @@ const CLA_DEVICE_INFO: u8 = 0xe0;
 const INS_DEVICE_INFO: u8 = 0x01;
 
 /// Execute a command with the provided transport
-async fn execute<T, E>(t: T, cmd: Commands) -> anyhow::Result<()> 
+async fn execute<T, E>(t: T, cmd: Commands, format: OutputFormat) -> anyhow::Result<()>
 where
     T: Exchange<Error=E>,
     E: Error + Sync + Send + 'static,
 {
+    // Eth commands chunk their own APDUs, so they're dispatched separately
+    if let Commands::Eth{ cmd } = cmd {
+        return eth::execute(&t, cmd, format).await;
+    }
+
     // Setup the command ADPU
     let command: APDUCommand<Vec<u8>> = match cmd {
         Commands::DeviceInfo => APDUCommand::new(CLA_DEVICE_INFO, INS_DEVICE_INFO),
@@ -103,8 +242,8 @@ where
     let response = t.exchange(&command).await?;
     match response.error_code() {
         Ok(APDUErrorCode::NoError) => {}
-        Ok(err) => return Err(anyhow::anyhow!("unhandled APDU response: {:?}", err)),
-        Err(err) => return Err(anyhow::anyhow!("unknown APDU response: {:?}", err)),
+        Ok(code) => return Err(ApduError::known(code, response.retcode()).into()),
+        Err(_) => return Err(ApduError::unknown(response.retcode()).into()),
     }
 
     // Handle response ADPU
@@ -114,15 +253,15 @@ where
     match cmd {
         Commands::DeviceInfo => {
             let device_info = DeviceInfo::try_from(response_data)?;
-            log::info!("device info: {:#?}", device_info);
+            print_result(format, &device_info)?;
         },
         Commands::AppInfo => {
             let app_info = AppInfo::try_from(response_data)?;
-            log::info!("app info: {:#?}", app_info);
+            print_result(format, &app_info)?;
         },
         Commands::AppVersion{ .. } => {
             let app_version = Version::try_from(response_data)?;
-            log::info!("app version: {:#?}", app_version);
+            print_result(format, &app_version)?;
         },
         _ => (),
     }
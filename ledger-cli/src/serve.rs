@@ -0,0 +1,131 @@
+//! APDU relay/bridge server: exposes a single underlying transport to multiple TCP clients so
+//! several processes can forward APDUs to one attached device, analogous to how `mozdevice`
+//! multiplexes access to a single device over the ADB wire protocol.
+//!
+//! Wire protocol is deliberately trivial: a 4-byte big-endian length prefix followed by the raw
+//! bytes (a serialized APDU command from the client, a serialized APDU answer in the reply).
+
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use ledger_transport::{APDUCommand, Exchange};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+
+/// Upper bound on a single framed message. A real APDU command/answer tops out at a 5-byte
+/// header plus 255 bytes of data, so a few KB leaves headroom without letting a garbled or
+/// malicious length prefix force a multi-gigabyte allocation.
+const MAX_FRAME_LEN: usize = 4096;
+
+/// Serve `transport` to TCP clients connecting to `bind`. Access to the underlying transport is
+/// serialized behind a mutex so concurrent clients can't interleave exchanges.
+pub async fn serve<T, E>(transport: T, bind: SocketAddr) -> anyhow::Result<()>
+where
+    T: Exchange<Error = E> + Send + Sync + 'static,
+    E: Error + Sync + Send + 'static,
+{
+    let listener = TcpListener::bind(bind).await?;
+    let transport = Arc::new(Mutex::new(transport));
+
+    log::info!("serving APDU relay on {}", bind);
+
+    loop {
+        let (socket, peer) = listener.accept().await?;
+        let transport = transport.clone();
+
+        tokio::spawn(async move {
+            log::debug!("client {} connected", peer);
+            if let Err(e) = handle_client(socket, transport).await {
+                log::warn!("client {} disconnected: {}", peer, e);
+            }
+        });
+    }
+}
+
+async fn handle_client<T, E>(mut socket: TcpStream, transport: Arc<Mutex<T>>) -> anyhow::Result<()>
+where
+    T: Exchange<Error = E>,
+    E: Error + Sync + Send + 'static,
+{
+    loop {
+        let mut len_buf = [0u8; 4];
+        if socket.read_exact(&mut len_buf).await.is_err() {
+            return Ok(()); // client closed the connection
+        }
+        let len = u32::from_be_bytes(len_buf) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(anyhow::anyhow!(
+                "framed message length {} exceeds max of {} bytes",
+                len,
+                MAX_FRAME_LEN
+            ));
+        }
+
+        let mut data = vec![0u8; len];
+        socket.read_exact(&mut data).await?;
+
+        let command = parse_apdu(&data)?;
+
+        let raw_answer = {
+            let transport = transport.lock().await;
+            let answer = transport
+                .exchange(&command)
+                .await
+                .map_err(|e| anyhow::anyhow!("transport error: {}", e))?;
+
+            let mut raw = answer.data().to_vec();
+            raw.extend_from_slice(&answer.retcode().to_be_bytes());
+            raw
+        };
+
+        socket.write_all(&(raw_answer.len() as u32).to_be_bytes()).await?;
+        socket.write_all(&raw_answer).await?;
+    }
+}
+
+/// Parse a raw `CLA INS P1 P2 [LC DATA]` APDU command off the wire
+fn parse_apdu(bytes: &[u8]) -> anyhow::Result<APDUCommand<Vec<u8>>> {
+    if bytes.len() < 4 {
+        return Err(anyhow::anyhow!("APDU frame too short: {} bytes", bytes.len()));
+    }
+
+    let data = match bytes.get(4) {
+        Some(&lc) => bytes
+            .get(5..5 + lc as usize)
+            .ok_or_else(|| anyhow::anyhow!("APDU data shorter than its length byte"))?
+            .to_vec(),
+        None => Vec::new(),
+    };
+
+    Ok(APDUCommand { cla: bytes[0], ins: bytes[1], p1: bytes[2], p2: bytes[3], data })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_apdu_parses_header_only_command() {
+        let command = parse_apdu(&[0xe0, 0x01, 0x00, 0x00]).unwrap();
+        assert_eq!((command.cla, command.ins, command.p1, command.p2), (0xe0, 0x01, 0x00, 0x00));
+        assert!(command.data.is_empty());
+    }
+
+    #[test]
+    fn parse_apdu_parses_command_with_data() {
+        let command = parse_apdu(&[0xe0, 0x02, 0x00, 0x00, 0x03, 0xaa, 0xbb, 0xcc]).unwrap();
+        assert_eq!(command.data, vec![0xaa, 0xbb, 0xcc]);
+    }
+
+    #[test]
+    fn parse_apdu_rejects_frame_shorter_than_header() {
+        assert!(parse_apdu(&[0xe0, 0x01, 0x00]).is_err());
+    }
+
+    #[test]
+    fn parse_apdu_rejects_data_shorter_than_lc() {
+        assert!(parse_apdu(&[0xe0, 0x01, 0x00, 0x00, 0x05, 0xaa]).is_err());
+    }
+}
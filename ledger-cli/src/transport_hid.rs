@@ -0,0 +1,348 @@
+//! USB HID transport, talking directly to a Ledger device via `hidapi-rusb`.
+//!
+//! This implements the Ledger HID protocol APDU framing (channel tag `0x0101`, a sequence
+//! counter, a 5-byte packet header with a 2-byte payload length on the first packet) rather
+//! than relying on a pre-built transport crate, so it can be paired with a simple single-device
+//! open path the same way the coins crate's `rusb` transport does.
+
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use clap::Parser;
+use hidapi::{HidApi, HidDevice};
+use ledger_transport::{APDUAnswer, APDUCommand, Exchange};
+use thiserror::Error;
+
+/// Ledger's USB vendor ID, used as the default device filter
+pub const LEDGER_VID: u16 = 0x2c97;
+
+const HID_CHANNEL: u16 = 0x0101;
+const HID_TAG_APDU: u8 = 0x05;
+const HID_PACKET_SIZE: usize = 64;
+
+/// Device selection filters for the HID transport
+#[derive(Clone, PartialEq, Debug, Parser)]
+pub struct HidOptions {
+    /// Filter connected devices by USB vendor ID
+    #[clap(long)]
+    pub vendor_id: Option<u16>,
+
+    /// Filter connected devices by USB product ID
+    #[clap(long)]
+    pub product_id: Option<u16>,
+}
+
+/// Errors produced by the HID transport
+#[derive(Debug, Error)]
+pub enum HidError {
+    #[error("hidapi error: {0}")]
+    Api(#[from] hidapi::HidError),
+
+    #[error("no ledger device found matching the given filters")]
+    NotFound,
+
+    #[error("more than one ledger device matches, use `list` or --vendor-id/--product-id to pick one")]
+    Ambiguous,
+
+    #[error("malformed HID APDU frame received from device")]
+    Framing,
+}
+
+/// A connected Ledger device, as reported by the `list` subcommand
+#[derive(Clone, Debug)]
+pub struct DeviceEntry {
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub serial_number: Option<String>,
+    pub product_string: Option<String>,
+    pub interface_number: i32,
+}
+
+/// Enumerate connected devices matching the given vendor/product filters
+pub fn list_devices(opts: &HidOptions) -> Result<Vec<DeviceEntry>, HidError> {
+    let api = HidApi::new()?;
+    let vendor_id = opts.vendor_id.unwrap_or(LEDGER_VID);
+
+    let devices = api
+        .device_list()
+        .filter(|d| d.vendor_id() == vendor_id)
+        .filter(|d| opts.product_id.map(|p| p == d.product_id()).unwrap_or(true))
+        .map(|d| DeviceEntry {
+            vendor_id: d.vendor_id(),
+            product_id: d.product_id(),
+            serial_number: d.serial_number().map(String::from),
+            product_string: d.product_string().map(String::from),
+            interface_number: d.interface_number(),
+        })
+        .collect();
+
+    Ok(devices)
+}
+
+/// USB HID transport implementing the Ledger wire protocol over 64-byte HID reports
+pub struct TransportHid {
+    device: Arc<Mutex<HidDevice>>,
+}
+
+impl TransportHid {
+    /// Open the single device matching `opts`, mirroring the coins crate's simplified
+    /// single-device `rusb` open path: zero or multiple matches is an error rather than a guess.
+    pub fn new(opts: HidOptions) -> Result<Self, HidError> {
+        let api = HidApi::new()?;
+        let vendor_id = opts.vendor_id.unwrap_or(LEDGER_VID);
+
+        let mut matches = api
+            .device_list()
+            .filter(|d| d.vendor_id() == vendor_id)
+            .filter(|d| opts.product_id.map(|p| p == d.product_id()).unwrap_or(true));
+
+        let info = matches.next().ok_or(HidError::NotFound)?;
+        if matches.next().is_some() {
+            return Err(HidError::Ambiguous);
+        }
+
+        let device = info.open_device(&api)?;
+
+        Ok(Self { device: Arc::new(Mutex::new(device)) })
+    }
+
+    /// Write `data` to the device, splitting it into 64-byte HID reports per the Ledger framing.
+    fn write_apdu(device: &HidDevice, data: &[u8]) -> Result<(), HidError> {
+        for packet in encode_packets(data) {
+            device.write(&packet)?;
+        }
+
+        Ok(())
+    }
+
+    /// Read and reassemble a full APDU answer from one or more 64-byte HID reports.
+    fn read_apdu(device: &HidDevice) -> Result<Vec<u8>, HidError> {
+        let mut reassembler = FrameReassembler::new();
+
+        loop {
+            let mut packet = [0u8; HID_PACKET_SIZE];
+            device.read(&mut packet)?;
+
+            if let Some(answer) = reassembler.feed(&packet)? {
+                return Ok(answer);
+            }
+        }
+    }
+}
+
+/// Split `data` into one or more 64-byte HID reports (plus a leading HID report-id byte) per the
+/// Ledger framing: a 5-byte header on every packet, with the first packet's header extended by a
+/// 2-byte payload length. Pulled out of `write_apdu` so the framing itself can be unit tested
+/// without a device.
+fn encode_packets(data: &[u8]) -> Vec<[u8; HID_PACKET_SIZE + 1]> {
+    let mut packets = Vec::new();
+    let mut sequence: u16 = 0;
+    let mut offset = 0;
+
+    while offset < data.len() || sequence == 0 {
+        let mut packet = [0u8; HID_PACKET_SIZE + 1]; // leading HID report-id byte
+        packet[1] = (HID_CHANNEL >> 8) as u8;
+        packet[2] = (HID_CHANNEL & 0xff) as u8;
+        packet[3] = HID_TAG_APDU;
+        packet[4] = (sequence >> 8) as u8;
+        packet[5] = (sequence & 0xff) as u8;
+
+        let mut header_len = 5;
+        if sequence == 0 {
+            packet[6] = (data.len() >> 8) as u8;
+            packet[7] = (data.len() & 0xff) as u8;
+            header_len = 7;
+        }
+
+        let chunk_len = usize::min(HID_PACKET_SIZE - header_len, data.len() - offset);
+        packet[1 + header_len..1 + header_len + chunk_len]
+            .copy_from_slice(&data[offset..offset + chunk_len]);
+
+        packets.push(packet);
+
+        offset += chunk_len;
+        sequence += 1;
+    }
+
+    packets
+}
+
+/// Reassembles a sequence of 64-byte HID reports (as read from the device, i.e. without the
+/// report-id byte `encode_packets` prepends) back into a full APDU answer. Pulled out of
+/// `read_apdu` so the reassembly and its validation can be unit tested without a device.
+struct FrameReassembler {
+    answer: Vec<u8>,
+    expected_len: Option<usize>,
+    sequence: u16,
+}
+
+impl FrameReassembler {
+    fn new() -> Self {
+        Self { answer: Vec::new(), expected_len: None, sequence: 0 }
+    }
+
+    /// Feed one HID report in. Returns `Ok(Some(answer))` once the full answer has been
+    /// reassembled, `Ok(None)` if more packets are still expected.
+    fn feed(&mut self, packet: &[u8; HID_PACKET_SIZE]) -> Result<Option<Vec<u8>>, HidError> {
+        if u16::from_be_bytes([packet[0], packet[1]]) != HID_CHANNEL || packet[2] != HID_TAG_APDU {
+            return Err(HidError::Framing);
+        }
+        if u16::from_be_bytes([packet[3], packet[4]]) != self.sequence {
+            return Err(HidError::Framing);
+        }
+
+        let mut offset = 5;
+        if self.sequence == 0 {
+            self.expected_len = Some(u16::from_be_bytes([packet[5], packet[6]]) as usize);
+            offset = 7;
+        }
+        let expected_len = self.expected_len.ok_or(HidError::Framing)?;
+
+        let remaining = expected_len - self.answer.len();
+        let chunk_len = usize::min(HID_PACKET_SIZE - offset, remaining);
+        self.answer.extend_from_slice(&packet[offset..offset + chunk_len]);
+
+        self.sequence += 1;
+
+        if self.answer.len() >= expected_len {
+            return Ok(Some(std::mem::take(&mut self.answer)));
+        }
+
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl Exchange for TransportHid {
+    type Error = HidError;
+    type AnswerType = Vec<u8>;
+
+    async fn exchange<I>(&self, command: &APDUCommand<I>) -> Result<APDUAnswer<Self::AnswerType>, Self::Error>
+    where
+        I: Deref<Target = [u8]> + Send + Sync,
+    {
+        let device = self.device.clone();
+        let apdu = command.serialize();
+
+        // The device handle is blocking, so run the whole exchange on a blocking-pool thread
+        // rather than stalling a tokio worker on a slow or unapproved-on-device request.
+        let answer = tokio::task::spawn_blocking(move || {
+            let device = device.lock().unwrap();
+            Self::write_apdu(&device, &apdu)?;
+            Self::read_apdu(&device)
+        })
+        .await
+        .map_err(|_| HidError::Framing)??;
+
+        APDUAnswer::from_answer(answer).map_err(|_| HidError::Framing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a well-formed HID report as read from the device (no leading report-id byte,
+    /// unlike `encode_packets`'s output which is written to the device).
+    fn report(sequence: u16, payload_len: Option<u16>, payload: &[u8]) -> [u8; HID_PACKET_SIZE] {
+        let mut packet = [0u8; HID_PACKET_SIZE];
+        packet[0] = (HID_CHANNEL >> 8) as u8;
+        packet[1] = (HID_CHANNEL & 0xff) as u8;
+        packet[2] = HID_TAG_APDU;
+        packet[3] = (sequence >> 8) as u8;
+        packet[4] = (sequence & 0xff) as u8;
+
+        let offset = match payload_len {
+            Some(len) => {
+                packet[5] = (len >> 8) as u8;
+                packet[6] = (len & 0xff) as u8;
+                7
+            }
+            None => 5,
+        };
+
+        packet[offset..offset + payload.len()].copy_from_slice(payload);
+        packet
+    }
+
+    #[test]
+    fn encode_packets_fits_in_a_single_packet() {
+        let packets = encode_packets(&[0xaa, 0xbb, 0xcc]);
+        assert_eq!(packets.len(), 1);
+        assert_eq!(&packets[0][1..8], &[0x01, 0x01, 0x05, 0x00, 0x00, 0x00, 0x03]);
+        assert_eq!(&packets[0][8..11], &[0xaa, 0xbb, 0xcc]);
+    }
+
+    #[test]
+    fn encode_packets_handles_empty_payload() {
+        let packets = encode_packets(&[]);
+        assert_eq!(packets.len(), 1);
+        assert_eq!(&packets[0][6..8], &[0x00, 0x00]);
+    }
+
+    #[test]
+    fn encode_packets_splits_across_the_first_packet_boundary() {
+        // First packet has a 7-byte header, leaving 57 bytes of data; one byte past that must
+        // spill into a second packet.
+        let data = vec![0x42; 58];
+        let packets = encode_packets(&data);
+        assert_eq!(packets.len(), 2);
+        assert_eq!(&packets[1][1..6], &[0x01, 0x01, 0x05, 0x00, 0x01]);
+        assert_eq!(&packets[1][6..7], &[0x42]);
+    }
+
+    #[test]
+    fn reassembler_handles_single_packet() {
+        let mut reassembler = FrameReassembler::new();
+        let packet = report(0, Some(3), &[0xaa, 0xbb, 0xcc]);
+        assert_eq!(reassembler.feed(&packet).unwrap(), Some(vec![0xaa, 0xbb, 0xcc]));
+    }
+
+    #[test]
+    fn reassembler_handles_exact_first_packet_boundary() {
+        // 64-byte packet, 7-byte header -> exactly 57 bytes of payload fit in the first packet.
+        let data = vec![0x7; 57];
+        let mut reassembler = FrameReassembler::new();
+        let packet = report(0, Some(57), &data);
+        assert_eq!(reassembler.feed(&packet).unwrap(), Some(data));
+    }
+
+    #[test]
+    fn reassembler_handles_multi_packet_reassembly() {
+        let mut reassembler = FrameReassembler::new();
+
+        let first = report(0, Some(60), &[0x1; 57]);
+        assert_eq!(reassembler.feed(&first).unwrap(), None);
+
+        let mut rest = vec![0x1; 57];
+        rest.extend_from_slice(&[0x2; 3]);
+        let second = report(1, None, &[0x2; 3]);
+        assert_eq!(reassembler.feed(&second).unwrap(), Some(rest));
+    }
+
+    #[test]
+    fn reassembler_rejects_wrong_channel() {
+        let mut packet = report(0, Some(1), &[0xaa]);
+        packet[0] = 0xff;
+        let mut reassembler = FrameReassembler::new();
+        assert!(matches!(reassembler.feed(&packet), Err(HidError::Framing)));
+    }
+
+    #[test]
+    fn reassembler_rejects_out_of_order_sequence() {
+        let packet = report(1, Some(1), &[0xaa]);
+        let mut reassembler = FrameReassembler::new();
+        assert!(matches!(reassembler.feed(&packet), Err(HidError::Framing)));
+    }
+
+    #[test]
+    fn reassembler_rejects_continuation_packet_before_first() {
+        // A sequence-1 packet has no 2-byte length prefix, so if it somehow arrived as the very
+        // first packet fed in, there's no `expected_len` to validate against.
+        let mut reassembler = FrameReassembler::new();
+        reassembler.sequence = 1;
+        let packet = report(1, None, &[0xaa]);
+        assert!(matches!(reassembler.feed(&packet), Err(HidError::Framing)));
+    }
+}